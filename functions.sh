@@ -4,3 +4,5 @@ alias tdo="cs ${HOME}/.data/personal/"
 alias tstart="todo timer start"
 alias tdone="todo timer done"
 alias tfailed="todo timer failed"
+alias tpause="todo timer pause"
+alias tresume="todo timer resume"
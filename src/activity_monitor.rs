@@ -1,11 +1,13 @@
 use crate::config::AppConfig;
-use anyhow::Result;
+use crate::utils;
+use anyhow::{Context, Result};
 use chrono::prelude::*;
+use clap::{Args, Subcommand};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::VecDeque;
-use std::fs::File;
-use std::io::{Read, Write};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
 use std::{
 	ffi::OsStr,
 	process::{Command, Output},
@@ -14,6 +16,49 @@ use std::{
 use crate::MONITOR_PATH_APPENDIX;
 use crate::TOTALS_PATH_APPENDIX;
 
+pub fn main(config: AppConfig, args: MonitorArgs) -> Result<()> {
+	if args.migrate {
+		return migrate_all(&config);
+	}
+	match args.command {
+		None => start(config),
+		Some(MonitorCommands::Summary(summary_args)) => summary(&config, summary_args),
+	}
+}
+
+#[derive(Args)]
+pub struct MonitorArgs {
+	#[command(subcommand)]
+	command: Option<MonitorCommands>,
+	/// Convert all recorded days still in the old json array format to NDJSON, then exit
+	#[arg(long)]
+	migrate: bool,
+}
+#[derive(Subcommand)]
+enum MonitorCommands {
+	/// Print total time per activity over a day, rolled up by the delimiter
+	Summary(SummaryArgs),
+}
+#[derive(Args)]
+struct SummaryArgs {
+	/// In `date_format` from the config. Defaults to today
+	#[arg(short, long)]
+	date: Option<String>,
+}
+
+fn summary(config: &AppConfig, args: SummaryArgs) -> Result<()> {
+	let date = args.date.unwrap_or_else(|| Utc::now().format(config.date_format.as_str()).to_string());
+	let activities = read_day(config, &date);
+	if activities.is_empty() {
+		println!("no data");
+		return Ok(());
+	}
+
+	let total = Total::from_activities(activities, &config.activity_monitor.delimitor);
+	print!("{}", total.summary());
+	Ok(())
+}
+
 pub fn start(config: AppConfig) -> Result<()> {
 	let mut prev_activity_name = String::new();
 	let mut start_s = Utc::now().timestamp();
@@ -97,27 +142,107 @@ fn get_activity(config: &AppConfig) -> String {
 	"PC".to_owned() + config.activity_monitor.delimitor.as_str() + &activity
 }
 
-/// Incredibly inefficient way of recording a new entry, because we load all the existing ones first.
 fn record_activity(config: &AppConfig, name: String, start_s: i64, end_s: i64) {
 	let save_dir = config.data_dir.join(MONITOR_PATH_APPENDIX);
 
 	let record = Activity { name, start_s, end_s };
 
 	let date = Utc::now().format(config.date_format.as_str()).to_string();
-	let target_path = save_dir.join(&date);
-	let mut records: VecDeque<Activity> = match File::open(&target_path) {
-		Ok(mut file) => {
-			let mut contents = String::new();
-			file.read_to_string(&mut contents).unwrap();
-			serde_json::from_str(&contents).unwrap_or_else(|_| VecDeque::new())
+	append_activity(&save_dir.join(&date), &record).unwrap();
+}
+
+/// Day files are newline-delimited json, one `Activity` per line. Files still in the old json array format are converted on first append.
+fn append_activity(path: &Path, activity: &Activity) -> Result<()> {
+	migrate_day_file(path)?;
+
+	let mut file = OpenOptions::new().create(true).read(true).append(true).open(path)?;
+	// don't glue onto a line left unterminated by a crash
+	let mut last_byte = [0u8; 1];
+	if file.metadata()?.len() > 0 {
+		file.seek(SeekFrom::End(-1))?;
+		file.read_exact(&mut last_byte)?;
+		if last_byte[0] != b'\n' {
+			writeln!(file)?;
+		}
+	}
+	writeln!(file, "{}", serde_json::to_string(activity)?)?;
+	Ok(())
+}
+
+/// Rewrites a day file in the old json array format as NDJSON. Returns whether anything was converted.
+/// An array that fails to parse is never rewritten: it is moved aside to `.corrupt-<timestamp>` for manual recovery.
+fn migrate_day_file(path: &Path) -> Result<bool> {
+	let mut first_byte = [0u8; 1];
+	if File::open(path).and_then(|mut f| f.read(&mut first_byte)).is_err() || first_byte[0] != b'[' {
+		return Ok(false);
+	}
+
+	let activities: Vec<Activity> = match serde_json::from_str(&std::fs::read_to_string(path)?) {
+		Ok(a) => a,
+		Err(_) => {
+			utils::quarantine_corrupt(path).with_context(|| format!("Failed to move corrupted {} aside", path.display()))?;
+			return Ok(false);
 		}
-		Err(_) => VecDeque::new(),
 	};
-	records.push_back(record);
+	let mut contents = String::new();
+	for a in activities {
+		contents.push_str(&serde_json::to_string(&a)?);
+		contents.push('\n');
+	}
+	utils::atomic_write(path, contents)?;
+	Ok(true)
+}
 
-	let mut file = File::create(&target_path).unwrap();
-	let json = serde_json::to_string(&records).unwrap();
-	file.write_all(json.as_bytes()).unwrap();
+fn migrate_all(config: &AppConfig) -> Result<()> {
+	let mut n_migrated = 0;
+	let dir = config.data_dir.join(MONITOR_PATH_APPENDIX);
+	for entry in std::fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+		let path = entry?.path();
+		// leftovers of `utils::atomic_write` and `utils::quarantine_corrupt`, not day files
+		let name = path.file_name().unwrap_or_default().to_string_lossy();
+		if name.contains(".corrupt-") || name.contains(".tmp-") {
+			continue;
+		}
+		if path.is_file() && migrate_day_file(&path)? {
+			n_migrated += 1;
+		}
+	}
+	println!("Migrated {} day file(s) to NDJSON", n_migrated);
+	Ok(())
+}
+
+fn read_day(config: &AppConfig, date: &str) -> Vec<Activity> {
+	Activity::read_day(&config.data_dir.join(MONITOR_PATH_APPENDIX).join(date))
+}
+
+impl Activity {
+	/// Reads both NDJSON and the old json array format. Missing file is an empty day.
+	/// Unparsable lines (eg one cut off by a crash mid-write) are skipped with a warning.
+	fn read_day(path: &Path) -> Vec<Self> {
+		let contents = match std::fs::read_to_string(path) {
+			Ok(c) => c,
+			Err(_) => return Vec::new(),
+		};
+
+		if contents.trim_start().starts_with('[') {
+			return serde_json::from_str(&contents).unwrap_or_else(|e| {
+				eprintln!("Warning: skipping {}, failed to parse: {}", path.display(), e);
+				Vec::new()
+			});
+		}
+		contents
+			.lines()
+			.enumerate()
+			.filter(|(_, l)| !l.trim().is_empty())
+			.filter_map(|(i, l)| match serde_json::from_str(l) {
+				Ok(a) => Some(a),
+				Err(e) => {
+					eprintln!("Warning: skipping line {} of {}: {}", i + 1, path.display(), e);
+					None
+				}
+			})
+			.collect()
+	}
 }
 
 //-----------------------------------------------------------------------------
@@ -130,19 +255,14 @@ fn compile_yd_totals(config: &AppConfig) {
 		return;
 	};
 
-	let yd_activities_file = (config.data_dir.join(MONITOR_PATH_APPENDIX)).join(&date_yd);
-	let file_contents = match std::fs::read_to_string(&yd_activities_file) {
-		Ok(c) => c,
-		Err(_) => "[]".to_owned(),
-	};
-	let yd_activities: Vec<Activity> = serde_json::from_str(&file_contents).unwrap();
+	let yd_activities: Vec<Activity> = read_day(config, &date_yd);
 
 	fn write_grand_total(yd_activities: Vec<Activity>, config: &AppConfig) {
 		let grand_total = Total::from_activities(yd_activities, &config.activity_monitor.delimitor);
 
 		let formatted_json = serde_json::to_string_pretty(&grand_total).unwrap();
-		let mut file = std::fs::File::create(config.data_dir.join(TOTALS_PATH_APPENDIX).join("Grand Total")).unwrap(); //NB: replaces the existing if any
-		file.write_all(formatted_json.as_bytes()).unwrap();
+		utils::atomic_write(config.data_dir.join(TOTALS_PATH_APPENDIX).join("Grand Total"), formatted_json).unwrap();
+		//NB: replaces the existing if any
 	}
 	write_grand_total(yd_activities.clone(), config);
 
@@ -225,6 +345,7 @@ fn compile_yd_totals(config: &AppConfig) {
 	compile_calendar(yd_activities.clone(), config);
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 struct GoogleServiceAccount {
 	r#type: String,
@@ -289,7 +410,7 @@ impl Total {
 				Some(index) => index,
 				None => {
 					grand_total.children[l0_index].children.push(Total::new(l1_name));
-					grand_total.children.len() - 1
+					grand_total.children[l0_index].children.len() - 1
 				}
 			};
 			{
@@ -305,7 +426,7 @@ impl Total {
 					Some(index) => index,
 					None => {
 						grand_total.children[l0_index].children[l1_index].children.push(Total::new(l2_name));
-						grand_total.children.len() - 1
+						grand_total.children[l0_index].children[l1_index].children.len() - 1
 					}
 				};
 				{
@@ -336,4 +457,157 @@ impl Total {
 			largest.find_largest(collect_str, _activities_delimiter)
 		}
 	}
+
+	/// One line per node: name indented by depth, hh:mm, and share of the whole. Children sorted by time, descending.
+	fn summary(&self) -> String {
+		fn push_lines(t: &Total, depth: usize, whole_s: i64, s: &mut String) {
+			let name = format!("{}{}", "  ".repeat(depth), t.name);
+			let percent = match whole_s {
+				0 => 0.0,
+				_ => t.time_s as f64 / whole_s as f64 * 100.0,
+			};
+			s.push_str(&format!(
+				"{:<60} {:02}:{:02} {:>5.1}%\n",
+				name,
+				t.time_s / 3600,
+				t.time_s % 3600 / 60,
+				percent
+			));
+
+			let mut children: Vec<&Total> = t.children.iter().collect();
+			children.sort_by_key(|c| std::cmp::Reverse(c.time_s));
+			for c in children {
+				push_lines(c, depth + 1, whole_s, s);
+			}
+		}
+
+		let mut s = String::new();
+		push_lines(self, 0, self.time_s, &mut s);
+		s
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn activity(name: &str, start_s: i64) -> Activity {
+		Activity {
+			name: name.to_owned(),
+			start_s,
+			end_s: start_s + 60,
+		}
+	}
+
+	#[test]
+	fn test_append_migrates_array_format() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("2024-06-12");
+		let old = vec![activity("PC - Tmux", 0), activity("PC - Google", 60)];
+		std::fs::write(&path, serde_json::to_string(&old).unwrap()).unwrap();
+
+		append_activity(&path, &activity("PC - Telegram", 120)).unwrap();
+
+		let contents = std::fs::read_to_string(&path).unwrap();
+		assert_eq!(contents.lines().count(), 3);
+		let names: Vec<String> = Activity::read_day(&path).into_iter().map(|a| a.name).collect();
+		assert_eq!(names, vec!["PC - Tmux", "PC - Google", "PC - Telegram"]);
+	}
+
+	#[test]
+	fn test_migrate_keeps_truncated_array() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("2024-06-12");
+		let old = serde_json::to_string(&vec![activity("PC - Tmux", 0), activity("PC - Google", 60)]).unwrap();
+		let truncated = &old[..old.len() - 10];
+		std::fs::write(&path, truncated).unwrap();
+
+		append_activity(&path, &activity("PC - Telegram", 120)).unwrap();
+
+		let quarantined: Vec<_> = std::fs::read_dir(dir.path())
+			.unwrap()
+			.map(|e| e.unwrap().path())
+			.filter(|p| p.file_name().unwrap().to_str().unwrap().starts_with("2024-06-12.corrupt-"))
+			.collect();
+		assert_eq!(quarantined.len(), 1);
+		assert_eq!(std::fs::read_to_string(&quarantined[0]).unwrap(), truncated);
+		let names: Vec<String> = Activity::read_day(&path).into_iter().map(|a| a.name).collect();
+		assert_eq!(names, vec!["PC - Telegram"]);
+	}
+
+	#[test]
+	fn test_migrate_all_skips_quarantined() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = AppConfig {
+			data_dir: dir.path().to_path_buf(),
+			..Default::default()
+		};
+		let monitor_dir = config.data_dir.join(MONITOR_PATH_APPENDIX);
+		std::fs::create_dir_all(&monitor_dir).unwrap();
+		std::fs::write(monitor_dir.join("2024-06-12"), r#"[{"name":"PC - Tmux","#).unwrap();
+
+		for _ in 0..3 {
+			migrate_all(&config).unwrap();
+		}
+
+		let names: Vec<String> = std::fs::read_dir(&monitor_dir)
+			.unwrap()
+			.map(|e| e.unwrap().file_name().into_string().unwrap())
+			.collect();
+		assert_eq!(names.len(), 1);
+		assert_eq!(names[0].matches(".corrupt-").count(), 1, "{:?}", names);
+	}
+
+	#[test]
+	fn test_read_day_skips_partial_line() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("2024-06-12");
+		append_activity(&path, &activity("PC - Tmux", 0)).unwrap();
+		append_activity(&path, &activity("PC - Google", 60)).unwrap();
+		// process killed mid-write
+		let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+		file.write_all(br#"{"name":"PC - Teleg"#).unwrap();
+
+		let names: Vec<String> = Activity::read_day(&path).into_iter().map(|a| a.name).collect();
+		assert_eq!(names, vec!["PC - Tmux", "PC - Google"]);
+
+		append_activity(&path, &activity("PC - Discord", 120)).unwrap();
+		let names: Vec<String> = Activity::read_day(&path).into_iter().map(|a| a.name).collect();
+		assert_eq!(names, vec!["PC - Tmux", "PC - Google", "PC - Discord"]);
+	}
+
+	#[test]
+	fn test_summary_rolls_up() {
+		let activities = vec![
+			Activity {
+				name: "PC - Google - docs".to_owned(),
+				start_s: 0,
+				end_s: 30 * 60,
+			},
+			Activity {
+				name: "PC - Tmux".to_owned(),
+				start_s: 30 * 60,
+				end_s: 60 * 60,
+			},
+			Activity {
+				name: "PC - Google - mail".to_owned(),
+				start_s: 60 * 60,
+				end_s: 120 * 60,
+			},
+		];
+		let summary = Total::from_activities(activities, &" - ".to_owned()).summary();
+		let lines: Vec<Vec<&str>> = summary.lines().map(|l| l.split_whitespace().collect()).collect();
+
+		assert_eq!(
+			lines,
+			vec![
+				vec!["Total", "02:00", "100.0%"],
+				vec!["PC", "02:00", "100.0%"],
+				vec!["Google", "01:30", "75.0%"],
+				vec!["mail", "01:00", "50.0%"],
+				vec!["docs", "00:30", "25.0%"],
+				vec!["Tmux", "00:30", "25.0%"],
+			]
+		);
+	}
 }
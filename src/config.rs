@@ -1,4 +1,5 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
 use serde::Deserialize;
 use std::path::PathBuf;
 use v_utils::io::ExpandedPath;
@@ -27,19 +28,323 @@ pub struct ActivityMonitor {
 }
 #[derive(Default, Clone, derive_new::new, Debug, Deserialize)]
 pub struct Timer {
+	#[serde(default = "default_hard_stop_coeff")]
 	pub hard_stop_coeff: f32,
+	/// Anything above is rejected by `timer start`
+	#[serde(default = "default_max_estimate_minutes")]
+	pub max_estimate_minutes: u32,
+	#[serde(default = "default_categories")]
+	pub categories: Vec<String>,
+	#[serde(default)]
+	pub sink: TimerSinkKind,
+}
+/// Where the running timer is displayed
+#[derive(Default, Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimerSinkKind {
+	#[default]
+	Eww,
+	/// For headless runs
+	Null,
+}
+fn default_hard_stop_coeff() -> f32 {
+	1.5
+}
+fn default_max_estimate_minutes() -> u32 {
+	90
+}
+fn default_categories() -> Vec<String> {
+	["rust", "go", "python", "home", "workout", "library", "git issue"]
+		.iter()
+		.map(|s| s.to_string())
+		.collect()
 }
 
 impl AppConfig {
-	pub fn read(path: ExpandedPath) -> Result<Self, config::ConfigError> {
-		let builder = config::Config::builder().add_source(config::File::with_name(&path.to_string()));
-
-		let settings: config::Config = builder.build()?;
-		let settings: Self = settings.try_deserialize()?;
+	/// `data_dir`, if provided, takes precedence over the one in the config file.
+	pub fn read(path: ExpandedPath, data_dir: Option<ExpandedPath>) -> Result<Self, config::ConfigError> {
+		let settings = Self::from_raw(Self::raw(&path)?, data_dir)?;
 
 		let _ = std::fs::create_dir_all(&settings.data_dir);
 		let _ = std::fs::create_dir_all(settings.data_dir.join("tmp/"));
 
 		Ok(settings)
 	}
+
+	fn raw(path: &ExpandedPath) -> Result<config::Config, config::ConfigError> {
+		config::Config::builder().add_source(config::File::with_name(&path.to_string())).build()
+	}
+
+	fn from_raw(raw: config::Config, data_dir: Option<ExpandedPath>) -> Result<Self, config::ConfigError> {
+		let mut settings: Self = raw.try_deserialize()?;
+		if let Some(data_dir) = data_dir {
+			settings.data_dir = data_dir.0;
+		}
+		Ok(settings)
+	}
+}
+
+#[derive(Args)]
+pub struct ConfigArgs {
+	#[command(subcommand)]
+	command: ConfigCommands,
+}
+#[derive(Subcommand)]
+enum ConfigCommands {
+	/// Validate the config up front, instead of failing in the middle of a command
+	Check,
+}
+
+/// Runs before the config is loaded, as a config that fails to load is exactly what `check` is for.
+pub fn command(path: ExpandedPath, data_dir: Option<ExpandedPath>, args: ConfigArgs) -> Result<()> {
+	match args.command {
+		ConfigCommands::Check => {
+			let checklist = check_file(&path, data_dir)?;
+			for (name, status) in &checklist {
+				match status {
+					Ok(()) => println!("[OK]      {}", name),
+					Err(problem) => println!("[MISSING] {}: {}", name, problem),
+				}
+			}
+
+			let n_problems = checklist.iter().filter(|(_, status)| status.is_err()).count();
+			match n_problems {
+				0 => Ok(()),
+				n => Err(anyhow!("{} problem(s) found in the config", n)),
+			}
+		}
+	}
+}
+
+/// `{ env = "VAR" }` values whose variable is unset, as (dotted key, variable name).
+fn unset_env_vars(value: &serde_json::Value, prefix: &str) -> Vec<(String, String)> {
+	let serde_json::Value::Object(map) = value else {
+		return Vec::new();
+	};
+	if let (1, Some(serde_json::Value::String(var))) = (map.len(), map.get("env")) {
+		return match std::env::var(var) {
+			Ok(_) => Vec::new(),
+			Err(_) => vec![(prefix.to_owned(), var.to_owned())],
+		};
+	}
+	map.iter()
+		.flat_map(|(key, v)| {
+			let key = match prefix {
+				"" => key.to_owned(),
+				_ => format!("{}.{}", prefix, key),
+			};
+			unset_env_vars(v, &key)
+		})
+		.collect()
+}
+
+/// Unset env-backed values are reported by key, then blanked so the rest of the config can still be loaded and checked.
+fn check_file(path: &ExpandedPath, data_dir: Option<ExpandedPath>) -> Result<Vec<(String, Result<(), String>)>> {
+	let raw = AppConfig::raw(path)?;
+	let unset_env = unset_env_vars(&raw.clone().try_deserialize::<serde_json::Value>()?, "");
+
+	let mut checklist: Vec<(String, Result<(), String>)> = unset_env
+		.iter()
+		.map(|(key, var)| (key.clone(), Err(format!("environment variable {} is not set", var))))
+		.collect();
+
+	let mut builder = config::Config::builder().add_source(raw);
+	for (key, _) in &unset_env {
+		builder = builder.set_override(key.as_str(), "")?;
+	}
+	match builder.build().and_then(|raw| AppConfig::from_raw(raw, data_dir)) {
+		Ok(config) => checklist.extend(
+			config
+				.checklist()
+				.into_iter()
+				.filter(|(name, _)| !unset_env.iter().any(|(key, _)| key == name))
+				.map(|(name, status)| (name.to_owned(), status)),
+		),
+		Err(e) => checklist.push(("config".to_owned(), Err(e.to_string()))),
+	}
+	Ok(checklist)
+}
+
+impl AppConfig {
+	/// Everything that is otherwise only found to be wrong deep inside a command.
+	pub fn checklist(&self) -> Vec<(&'static str, Result<(), String>)> {
+		fn non_empty(s: &str) -> Result<(), String> {
+			match s.is_empty() {
+				true => Err("empty".to_owned()),
+				false => Ok(()),
+			}
+		}
+		fn exists(path: &std::path::Path) -> Result<(), String> {
+			match path.exists() {
+				true => Ok(()),
+				false => Err(format!("{} does not exist", path.display())),
+			}
+		}
+		fn env(name: &str) -> Result<(), String> {
+			std::env::var(name).map(|_| ()).map_err(|_| "environment variable is not set".to_owned())
+		}
+
+		let date_format = match chrono::format::StrftimeItems::new(&self.date_format).any(|i| i == chrono::format::Item::Error) {
+			true => Err(format!("\"{}\" is not a valid strftime format", self.date_format)),
+			false => non_empty(&self.date_format),
+		};
+		let hard_stop_coeff = match self.timer.hard_stop_coeff >= 1.0 {
+			true => Ok(()),
+			false => Err(format!(
+				"{} is below 1.0, so tasks would be stopped before the estimate",
+				self.timer.hard_stop_coeff
+			)),
+		};
+		let categories = match self.timer.categories.is_empty() {
+			true => Err("no categories configured".to_owned()),
+			false => Ok(()),
+		};
+
+		vec![
+			("data_dir", exists(&self.data_dir)),
+			("date_format", date_format),
+			("todos.path", exists(&self.todos.path)),
+			("timer.hard_stop_coeff", hard_stop_coeff),
+			("timer.categories", categories),
+			("activity_monitor.calendar_id", non_empty(&self.activity_monitor.calendar_id)),
+			(
+				"activity_monitor.google_calendar_refresh_token",
+				non_empty(&self.activity_monitor.google_calendar_refresh_token),
+			),
+			("activity_monitor.google_client_id", non_empty(&self.activity_monitor.google_client_id)),
+			(
+				"activity_monitor.google_client_secret",
+				non_empty(&self.activity_monitor.google_client_secret),
+			),
+			("$WAKETIME", env("WAKETIME")),
+			("$DAY_SECTION_BORDERS", env("DAY_SECTION_BORDERS")),
+		]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_checklist_missing_token() {
+		let config = AppConfig {
+			date_format: "%Y-%m-%d".to_owned(),
+			activity_monitor: ActivityMonitor {
+				calendar_id: "id".to_owned(),
+				google_client_id: "id".to_owned(),
+				google_client_secret: "secret".to_owned(),
+				..Default::default()
+			},
+			..Default::default()
+		};
+		let checklist = config.checklist();
+
+		let status = |name: &str| checklist.iter().find(|(n, _)| *n == name).unwrap().1.clone();
+		assert_eq!(status("activity_monitor.google_calendar_refresh_token"), Err("empty".to_owned()));
+		assert_eq!(status("activity_monitor.google_client_id"), Ok(()));
+		assert_eq!(status("date_format"), Ok(()));
+	}
+
+	#[test]
+	fn test_check_file_missing_token() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("config.toml");
+		std::fs::write(
+			&path,
+			r#"
+data_dir = "/tmp/todo_test_check_file"
+date_format = "%Y-%m-%d"
+[todos]
+path = "/tmp"
+n_tasks_to_show = 3
+[timer]
+[activity_monitor]
+delimitor = " - "
+calendar_id = "id"
+google_client_id = "id"
+google_client_secret = "secret"
+"#,
+		)
+		.unwrap();
+
+		let checklist = check_file(&ExpandedPath(path), None).unwrap();
+		assert_eq!(checklist.len(), 1);
+		let (name, status) = &checklist[0];
+		assert_eq!(name, "config");
+		assert!(status.as_ref().unwrap_err().contains("google_calendar_refresh_token"));
+	}
+
+	#[test]
+	fn test_check_file_unset_env_token() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("config.toml");
+		std::fs::write(
+			&path,
+			format!(
+				r#"
+data_dir = "{}"
+date_format = "%Y-%m-%d"
+[todos]
+path = "/tmp"
+n_tasks_to_show = 3
+[timer]
+[activity_monitor]
+delimitor = " - "
+calendar_id = "id"
+google_client_id = "id"
+google_client_secret = "secret"
+google_calendar_refresh_token = {{ env = "TODO_TEST_UNSET_REFRESH_TOKEN" }}
+"#,
+				dir.path().display()
+			),
+		)
+		.unwrap();
+
+		let checklist = check_file(&ExpandedPath(path), None).unwrap();
+		let status = |name: &str| checklist.iter().find(|(n, _)| n == name).unwrap().1.clone();
+		assert_eq!(
+			status("activity_monitor.google_calendar_refresh_token"),
+			Err("environment variable TODO_TEST_UNSET_REFRESH_TOKEN is not set".to_owned())
+		);
+		assert_eq!(status("activity_monitor.google_client_id"), Ok(()));
+		assert_eq!(status("date_format"), Ok(()));
+		assert_eq!(
+			checklist
+				.iter()
+				.filter(|(n, _)| n == "activity_monitor.google_calendar_refresh_token")
+				.count(),
+			1
+		);
+	}
+
+	#[test]
+	fn test_data_dir_override() {
+		let raw = || {
+			config::Config::builder()
+				.add_source(config::File::from_str(
+					r#"
+data_dir = "/from/file"
+date_format = "%Y-%m-%d"
+[todos]
+path = "/tmp"
+n_tasks_to_show = 3
+[timer]
+[activity_monitor]
+delimitor = " - "
+calendar_id = "id"
+google_calendar_refresh_token = "token"
+google_client_id = "id"
+google_client_secret = "secret"
+"#,
+					config::FileFormat::Toml,
+				))
+				.build()
+				.unwrap()
+		};
+
+		assert_eq!(AppConfig::from_raw(raw(), None).unwrap().data_dir, PathBuf::from("/from/file"));
+		let overridden = AppConfig::from_raw(raw(), Some(ExpandedPath(PathBuf::from("/from/cli")))).unwrap();
+		assert_eq!(overridden.data_dir, PathBuf::from("/from/cli"));
+	}
 }
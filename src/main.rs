@@ -6,7 +6,7 @@ pub mod mocks;
 mod timer;
 mod todos;
 pub mod utils;
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand};
 use config::AppConfig;
 use v_utils::io::ExpandedPath;
 
@@ -23,6 +23,9 @@ struct Cli {
 	command: Commands,
 	#[arg(long, default_value = "~/.config/todo.toml")]
 	config: ExpandedPath,
+	/// Overrides `data_dir` from the config
+	#[arg(long)]
+	data_dir: Option<ExpandedPath>,
 }
 
 #[derive(Subcommand)]
@@ -51,48 +54,66 @@ enum Commands {
 	///```
 	Quickfix(NoArgs),
 	/// Record day's ev and other stats.
-	///Following records ev of 420 for yesterday, then opens the file.
+	///Following records ev of 420 for yesterday, then opens the file. `-y` is the same as `-d1`.
 	///```rust
-	///todo manual -d1 --ev 420 -o
+	///todo manual -d1 ev 420 -o
 	///```
 	Manual(manual_stats::ManualArgs),
 	/// Start a task with timer, then store error (to track improvement of your estimations of time spent on different task categories)
 	///  Ex:
 	///'''rust
-	///todo do start -t=15 -w --description==do-da-work
+	///todo do start -t=15 -c rust --description==do-da-work
 	///. . . // start doing the task, then:
 	///todo do done
 	///'''
 	Timer(timer::TimerArgs),
 	/// Start monitoring user activities
-	Monitor(NoArgs),
+	///  Ex: time spent on each activity on a given day
+	///```rust
+	///todo monitor summary --date 2024-06-11
+	///```
+	Monitor(activity_monitor::MonitorArgs),
+	/// Operations on the config itself
+	///  Ex:
+	///```rust
+	///todo config check
+	///```
+	Config(config::ConfigArgs),
+	/// Print shell completions to stdout
+	///  Ex:
+	///```sh
+	///todo completions fish > ~/.config/fish/completions/todo.fish
+	///```
+	Completions(CompletionsArgs),
+}
+#[derive(Args)]
+struct CompletionsArgs {
+	shell: clap_complete::Shell,
 }
 #[derive(Args)]
 struct NoArgs {}
 
 fn main() {
 	let cli = Cli::parse();
+	// Only loaded by the commands that need it: `completions` and `config check` have to work without a valid config.
+	let read_config = || -> anyhow::Result<AppConfig> { Ok(AppConfig::read(cli.config.clone(), cli.data_dir.clone())?) };
 
-	let config = match AppConfig::read(cli.config) {
-		Ok(cfg) => cfg,
-		Err(e) => {
-			eprintln!("Error: {}", e);
-			std::process::exit(1);
-		}
-	};
-
-	// All the functions here can rely on config being correct.
 	let success = match cli.command {
-		Commands::Open(open_args) => {
+		Commands::Open(open_args) => read_config().and_then(|config| {
 			let mut todos_flags = open_args.shared;
 			todos_flags.open = true;
 			todos::open_or_add(config, todos_flags, None)
+		}),
+		Commands::Add(add_args) => read_config().and_then(|config| todos::open_or_add(config, add_args.shared, Some(add_args.name))),
+		Commands::Quickfix(_) => read_config().and_then(todos::compile_quickfix),
+		Commands::Manual(manual_args) => read_config().and_then(|config| manual_stats::update_or_open(config, manual_args)),
+		Commands::Timer(timer_args) => read_config().and_then(|config| timer::timing_the_task(config, timer_args)),
+		Commands::Monitor(monitor_args) => read_config().and_then(|config| activity_monitor::main(config, monitor_args)),
+		Commands::Config(config_args) => config::command(cli.config.clone(), cli.data_dir.clone(), config_args),
+		Commands::Completions(args) => {
+			clap_complete::generate(args.shell, &mut Cli::command(), "todo", &mut std::io::stdout());
+			Ok(())
 		}
-		Commands::Add(add_args) => todos::open_or_add(config, add_args.shared, Some(add_args.name)),
-		Commands::Quickfix(_) => todos::compile_quickfix(config),
-		Commands::Manual(manual_args) => manual_stats::update_or_open(config, manual_args),
-		Commands::Timer(timer_args) => timer::timing_the_task(config, timer_args),
-		Commands::Monitor(_) => activity_monitor::start(config),
 	};
 
 	match success {
@@ -103,3 +124,20 @@ fn main() {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_cli() {
+		Cli::command().debug_assert();
+
+		let mut completions = Vec::new();
+		clap_complete::generate(clap_complete::Shell::Zsh, &mut Cli::command(), "todo", &mut completions);
+		let completions = String::from_utf8(completions).unwrap();
+		assert!(completions.contains("#compdef todo"));
+		assert!(completions.contains("completions:Print shell completions to stdout"));
+		assert!(completions.contains("bash elvish fish powershell zsh"));
+	}
+}
@@ -1,13 +1,12 @@
 #![allow(non_snake_case)]
 use crate::config::AppConfig;
 use crate::utils;
-use anyhow::{anyhow, ensure, Result};
+use anyhow::{anyhow, ensure, Context, Result};
 use clap::Args;
 use clap::Subcommand;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::fs::OpenOptions;
-use std::io::Write;
+use std::io::BufRead;
 use std::path::Path;
 use std::path::PathBuf;
 use v_utils::{
@@ -19,12 +18,27 @@ static PBS_FILENAME: &str = ".pbs.json";
 
 use crate::MANUAL_PATH_APPENDIX;
 pub fn update_or_open(config: AppConfig, args: ManualArgs) -> Result<()> {
-	let date = utils::format_date(args.days_back, &config);
+	update_or_open_from(config, args, std::io::stdin().lock())
+}
+
+/// `input` is where `ev` is prompted from when not passed as an argument.
+fn update_or_open_from<R: BufRead>(config: AppConfig, args: ManualArgs, input: R) -> Result<()> {
+	if let ManualSubcommands::Show(show_args) = &args.command {
+		return show(&config, show_args.days, args.days_back()?);
+	}
+
+	let date = utils::format_date(args.days_back()?, &config);
 
 	let target_file_path = Day::path(&date, &config);
 
-	if let ManualSubcommands::Open(open_args) = &args.command {
-		match open_args.pbs {
+	// `ev` without a value but with `-o` just opens the day, same as `open`
+	let open_pbs = match &args.command {
+		ManualSubcommands::Open(open_args) => Some(open_args.pbs),
+		ManualSubcommands::Ev(ManualEv { ev: None, open: true, .. }) => Some(false),
+		_ => None,
+	};
+	if let Some(pbs) = open_pbs {
+		match pbs {
 			false => {
 				if !target_file_path.exists() {
 					return Err(anyhow!("Tried to open ev file of a day that was not initialized"));
@@ -40,22 +54,21 @@ pub fn update_or_open(config: AppConfig, args: ManualArgs) -> Result<()> {
 	}
 
 	let ev_override = match &args.command {
-		ManualSubcommands::Ev(ev) => Some(ev.validate()?),
+		ManualSubcommands::Ev(ev) => Some(ev.validate(input)?),
 		_ => None,
 	};
 
-	let day = match Day::load(&date, &config) {
-		Ok(d) => {
-			let mut d: Day = d;
+	// Existing day is always updated in place, never reset; if it can't be parsed, that's an error.
+	let day = match target_file_path.exists() {
+		true => {
+			let mut d = Day::load(&date, &config)?;
 
 			if let Some(ev_args) = &ev_override {
-				let ev = match (ev_args.add, ev_args.subtract) {
-					(true, false) => d.ev + ev_args.ev,
-					(false, true) => d.ev - ev_args.ev,
-					(false, false) => ev_args.ev,
-					(true, true) => unreachable!(),
+				d.ev = match ev_args.mode {
+					EvMode::Add => d.ev + ev_args.ev,
+					EvMode::Subtract => d.ev - ev_args.ev,
+					EvMode::Replace => ev_args.ev,
 				};
-				d.ev = ev;
 			} else if let ManualSubcommands::CounterStep(step) = &args.command {
 				if step.cargo_watch {
 					d.counters.cargo_watch += 1;
@@ -63,15 +76,17 @@ pub fn update_or_open(config: AppConfig, args: ManualArgs) -> Result<()> {
 				if step.dev_runs {
 					d.counters.dev_runs += 1;
 				}
+			} else if let ManualSubcommands::Set(set_args) = &args.command {
+				d.set_fields(&set_args.fields)?;
 			}
 			d
 		}
-		Err(_) => {
+		false => {
 			let mut d = Day::default();
 			//? should this not be a match?
 			if let Some(ev_args) = &ev_override {
 				ensure!(
-					ev_args.replace,
+					ev_args.mode == EvMode::Replace,
 					"The day object is not initialized, so `ev` argument must be provided with `-r --replace` flag"
 				);
 				d.ev = ev_args.ev;
@@ -83,23 +98,19 @@ pub fn update_or_open(config: AppConfig, args: ManualArgs) -> Result<()> {
 					d.counters.dev_runs = 1;
 				}
 				eprintln!("Initialized day object from a counter step. EV is set to 0. Don't forget to set it properly today.");
+			} else if let ManualSubcommands::Set(set_args) = &args.command {
+				d.set_fields(&set_args.fields)?;
+				eprintln!("Initialized day object from `set`. EV is set to 0. Don't forget to set it properly today.");
 			}
 
 			d.date = date.to_owned();
 			d
 		}
 	};
-	day.update_pbs(&target_file_path.parent().unwrap(), &config);
+	day.update_pbs(target_file_path.parent().unwrap(), &config)?;
 
 	let formatted_json = serde_json::to_string_pretty(&day).unwrap();
-	let mut file = OpenOptions::new()
-		.read(true)
-		.write(true)
-		.create(true)
-		.truncate(true)
-		.open(&target_file_path)
-		.unwrap();
-	file.write_all(formatted_json.as_bytes()).unwrap();
+	utils::atomic_write(&target_file_path, formatted_json).with_context(|| format!("Failed to write {}", target_file_path.display()))?;
 
 	if ev_override.is_some_and(|ev_args| ev_args.open) {
 		v_utils::io::open(&target_file_path)?;
@@ -114,26 +125,48 @@ fn process_manual_updates<T: AsRef<Path>>(path: T, config: &AppConfig) -> Result
 		return Err(anyhow!("File does not exist, likely because you manually changed something."));
 	}
 	let day: Day = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
-	day.update_pbs(path.as_ref().parent().unwrap(), config);
-	Ok(())
+	day.update_pbs(path.as_ref().parent().unwrap(), config)
 }
 
 #[derive(Args)]
 pub struct ManualArgs {
-	#[arg(short, long, default_value = "0")]
-	pub days_back: usize,
+	/// Defaults to 0, ie today
+	#[arg(short, long, visible_alias = "days-ago")]
+	pub days_back: Option<usize>,
+	/// Same as `-d 1`
+	#[arg(short, long)]
+	pub yesterday: bool,
 	#[command(subcommand)]
 	pub command: ManualSubcommands,
 }
+impl ManualArgs {
+	fn days_back(&self) -> Result<usize> {
+		match (self.days_back, self.yesterday) {
+			(Some(d), true) if d != 1 => Err(anyhow!("`-y` is the same as `-d 1`, but `-d {}` was also provided", d)),
+			(Some(d), _) => Ok(d),
+			(None, true) => Ok(1),
+			(None, false) => Ok(0),
+		}
+	}
+}
 #[derive(Subcommand)]
 pub enum ManualSubcommands {
 	Ev(ManualEv),
 	Open(ManualOpen),
 	CounterStep(CounterStep),
+	/// Set any fields of the day, keeping the rest as they were
+	///  Ex:
+	///```sh
+	///todo manual -y set morning.run=true evening.nsdr=20
+	///```
+	Set(ManualSet),
+	/// Print the last N days as a table, one row per field
+	Show(ManualShow),
 }
 #[derive(Args)]
 pub struct ManualEv {
-	pub ev: i32,
+	/// Prompted for on stdin if omitted
+	pub ev: Option<i32>,
 	#[arg(short, long)]
 	pub open: bool,
 	#[arg(short, long)]
@@ -145,25 +178,108 @@ pub struct ManualEv {
 }
 impl ManualEv {
 	//? This seems ugly. There has to be a way to do this natively with clap, specifically with the `conflicts_with` attribute
-	fn validate(&self) -> Result<Self> {
-		let replace = match self.add || self.subtract {
-			true => false,
-			false => self.replace,
+	/// If `ev` wasn't provided, it's read from `input`.
+	fn validate<R: BufRead>(&self, input: R) -> Result<ValidatedEv> {
+		let mode = match (self.add, self.subtract, self.replace) {
+			(true, false, _) => EvMode::Add,
+			(false, true, _) => EvMode::Subtract,
+			(false, false, true) => EvMode::Replace,
+			_ => return Err(anyhow!("Exactly one of 'add', 'subtract', or 'replace' must be specified.")),
+		};
+		let ev = match self.ev {
+			Some(ev) => ev,
+			None => prompt_ev(input)?,
 		};
-		if self.add && self.subtract {
-			return Err(anyhow!("Exactly one of 'add', 'subtract', or 'replace' must be specified."));
+		Ok(ValidatedEv { ev, mode, open: self.open })
+	}
+}
+
+struct ValidatedEv {
+	ev: i32,
+	mode: EvMode,
+	open: bool,
+}
+#[derive(Debug, PartialEq)]
+enum EvMode {
+	Add,
+	Subtract,
+	Replace,
+}
+
+fn prompt_ev<R: BufRead>(mut input: R) -> Result<i32> {
+	eprint!("ev: ");
+	let mut line = String::new();
+	input.read_line(&mut line)?;
+	let line = line.trim();
+	line.parse().map_err(|_| anyhow!("Could not parse ev from \"{}\"", line))
+}
+
+#[derive(Args)]
+pub struct ManualSet {
+	/// `path.to.field=value`, path as in the day's json file. Value is parsed as json, falling back to a string.
+	#[arg(required = true)]
+	pub fields: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct ManualShow {
+	#[arg(short, long, default_value = "7")]
+	pub days: usize,
+}
+
+fn show(config: &AppConfig, days: usize, days_back: usize) -> Result<()> {
+	fn flatten(prefix: &str, v: &serde_json::Value, out: &mut Vec<(String, String)>) {
+		match v {
+			serde_json::Value::Object(map) => {
+				for (k, v) in map {
+					let path = match prefix {
+						"" => k.to_owned(),
+						_ => format!("{}.{}", prefix, k),
+					};
+					flatten(&path, v, out);
+				}
+			}
+			serde_json::Value::Null => out.push((prefix.to_owned(), "-".to_owned())),
+			other => out.push((prefix.to_owned(), other.to_string())),
 		}
-		if !self.add && !self.subtract && !self.replace {
-			return Err(anyhow!("Exactly one of 'add', 'subtract', or 'replace' must be specified."));
+	}
+
+	let mut dates = Vec::new();
+	let mut columns = Vec::new();
+	for i in (days_back..days_back + days).rev() {
+		let date = utils::format_date(i, config);
+		if !Day::path(&date, config).exists() {
+			continue;
 		}
-		Ok(Self {
-			ev: self.ev,
-			open: self.open,
-			add: self.add,
-			subtract: self.subtract,
-			replace,
-		})
+		let mut fields = Vec::new();
+		flatten("", &serde_json::to_value(Day::load(&date, config)?)?, &mut fields);
+		dates.push(date);
+		columns.push(fields);
 	}
+	if dates.is_empty() {
+		println!("no data");
+		return Ok(());
+	}
+
+	let mut rows = Vec::new();
+	flatten("", &serde_json::to_value(Day::default())?, &mut rows);
+	let rows: Vec<String> = rows.into_iter().map(|(path, _)| path).filter(|path| path != "date").collect();
+	let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+
+	print!("{:<width$}", "", width = width);
+	for date in &dates {
+		print!(" {:>12}", date);
+	}
+	println!();
+	for row in rows {
+		print!("{:<width$}", row, width = width);
+		for fields in &columns {
+			let value = fields.iter().find(|(path, _)| *path == row).map_or("-", |(_, v)| v.as_str());
+			print!(" {:>12}", value);
+		}
+		println!();
+	}
+	Ok(())
 }
 
 #[derive(Args)]
@@ -183,12 +299,14 @@ pub struct CounterStep {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
 struct Transcendential {
 	making_food: Option<usize>,
 	eating_food: Option<usize>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
 struct Sleep {
 	yd_to_bed_t_plus: Option<i32>,
 	from_bed_t_plus: Option<i32>,
@@ -196,6 +314,7 @@ struct Sleep {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
 struct Morning {
 	alarm_to_run_M_colon_S: Option<Timelike>,
 	run: bool,
@@ -207,6 +326,7 @@ struct Morning {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
 // could be called `_8h`
 struct Midday {
 	hours_of_work: Option<usize>,
@@ -215,6 +335,7 @@ struct Midday {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
 struct Evening {
 	focus_meditation: usize, // fixed at 13m under current sota, but why not keep it flexible
 	nsdr: usize,
@@ -230,6 +351,7 @@ struct Evening {
 //}
 
 #[derive(Clone, Debug, Default, derive_new::new, Serialize, Deserialize)]
+#[serde(default)]
 struct Counters {
 	cargo_watch: usize,
 	dev_runs: usize,
@@ -237,6 +359,8 @@ struct Counters {
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 /// Unless specified otherwise, all times are in minutes
+/// Fields missing from a stored day (eg because it predates them) load as defaults.
+#[serde(default)]
 pub struct Day {
 	date: String,
 	ev: i32,
@@ -264,20 +388,43 @@ impl Day {
 	pub fn path(date: &str, config: &AppConfig) -> PathBuf {
 		let data_storage_dir = config.data_dir.clone().join(MANUAL_PATH_APPENDIX);
 		let _ = std::fs::create_dir(&data_storage_dir);
-		data_storage_dir.join(&format!("{}.json", date))
+		data_storage_dir.join(format!("{}.json", date))
+	}
+
+	/// Identify the day rather than record anything about it, so are not settable.
+	const IDENTITY_FIELDS: [&'static str; 1] = ["date"];
+
+	/// `assignments` are `path.to.field=value`. Fields are looked up in the serialized day, so only existing ones can be set.
+	fn set_fields(&mut self, assignments: &[String]) -> Result<()> {
+		let mut value = serde_json::to_value(&*self)?;
+		for assignment in assignments {
+			let (path, raw) = assignment
+				.split_once('=')
+				.ok_or_else(|| anyhow!("Expected `field=value`, got \"{}\"", assignment))?;
+			if Self::IDENTITY_FIELDS.contains(&path) {
+				return Err(anyhow!("\"{}\" is not a stats field and can't be set", path));
+			}
+			let target = path
+				.split('.')
+				.try_fold(&mut value, |v, key| v.get_mut(key))
+				.ok_or_else(|| anyhow!("Day has no field \"{}\"", path))?;
+			*target = serde_json::from_str(raw).unwrap_or_else(|_| serde_json::Value::String(raw.to_owned()));
+		}
+		*self = serde_json::from_value(value).with_context(|| format!("Invalid value in {:?}", assignments))?;
+		Ok(())
 	}
 
 	pub fn load(date: &str, config: &AppConfig) -> Result<Self> {
-		let target_file_path = Day::path(&date, &config);
+		let target_file_path = Day::path(date, config);
 		let file_contents: String = match std::fs::read_to_string(&target_file_path) {
 			Ok(s) => s,
 			Err(_) => "".to_owned(),
 		};
 
-		Ok(serde_json::from_str::<Day>(&file_contents)?)
+		serde_json::from_str::<Day>(&file_contents).with_context(|| format!("Failed to parse {}", target_file_path.display()))
 	}
 
-	fn update_pbs<T: AsRef<Path>>(&self, data_storage_dir: T, config: &AppConfig) {
+	fn update_pbs<T: AsRef<Path>>(&self, data_storage_dir: T, config: &AppConfig) -> Result<()> {
 		//TODO!!: fix error with adding extra brackets to ~/.data/personal/manual_stats/.pbs.json
 		fn announce_new_pb<T: std::fmt::Display>(new_value: &T, old_value: Option<&T>, name: &str) {
 			let old_value = match old_value {
@@ -286,13 +433,21 @@ impl Day {
 			};
 			let announcement = format!("New pb on {}! ({} -> {})", name, old_value, new_value);
 			println!("{}", announcement);
+			// fire-and-forget, so a slow notification daemon doesn't hold up `todo manual`; the child is reaped once we exit
+			#[allow(clippy::zombie_processes)]
 			std::process::Command::new("notify-send").arg(announcement).spawn().unwrap();
 		}
 
 		let pbs_path = data_storage_dir.as_ref().join(PBS_FILENAME);
 		let yd_date = utils::format_date(1, config); // no matter what file is being checked, we only ever care about physical yesterday
 		let mut pbs_as_value = match std::fs::read_to_string(&pbs_path) {
-			Ok(s) => serde_json::from_str::<serde_json::Value>(&s).unwrap(), // Value so we don't need to rewrite everything on `Day` struct changes. Both in terms of extra code, and recorded pb values. Previously had a Pbs struct, but that has proven to be unnecessary.
+			Ok(s) => match serde_json::from_str::<serde_json::Value>(&s) {
+				Ok(v) => v,
+				Err(_) => {
+					utils::quarantine_corrupt(&pbs_path).with_context(|| format!("Failed to move corrupted {} aside", pbs_path.display()))?;
+					serde_json::Value::Null
+				}
+			}, // Value so we don't need to rewrite everything on `Day` struct changes. Both in terms of extra code, and recorded pb values. Previously had a Pbs struct, but that has proven to be unnecessary.
 			Err(_) => serde_json::Value::Null,
 		};
 
@@ -341,24 +496,35 @@ impl Day {
 		let new_cw_counter = self.counters.cargo_watch;
 		conditional_update(&mut pbs_as_value, "cw_counter", new_cw_counter, |new, old| *new >= old + 10); // 10-step increments to prevent spam
 
+		let load_streaks_from = data_storage_dir.as_ref().join(format!("{}.json", yd_date));
+		let (yd_streaks_source, yd_unparsable) = match std::fs::read_to_string(&load_streaks_from) {
+			Ok(s) => match serde_json::from_str::<Day>(&s) {
+				Ok(d) => (Some(d), false),
+				Err(e) => {
+					eprintln!(
+						"Warning: failed to parse {} ({}), streaks are left as they are until it is fixed",
+						load_streaks_from.display(),
+						e
+					);
+					(None, true)
+				}
+			},
+			Err(_) => (None, false),
+		};
+
 		// Returns bool for convienience of recursing some of these
 		let mut streak_update = |metric: &str, condition: &dyn Fn(&Day) -> bool| -> bool {
-			let load_streaks_from = data_storage_dir.as_ref().join(&format!("{}.json", yd_date));
-			let yd_streaks_source = match std::fs::read_to_string(&load_streaks_from) {
-				Ok(s) => Some(serde_json::from_str::<Day>(&s).unwrap()),
-				Err(_) => None,
-			};
-
 			let pb_streaks = pbs_as_value.get("streaks").unwrap_or(&serde_json::Value::Null);
 			let read_streak: Streak = pb_streaks
 				.get(metric)
 				.map_or_else(Streak::default, |v| serde_json::from_value::<Streak>(v.clone()).unwrap_or_default());
 
-			let is_validated: bool = yd_streaks_source.is_some() && condition(&yd_streaks_source.unwrap());
-			let skip = match pb_streaks.get("__last_date_processed") {
-				Some(v) => v.as_str().expect("The only way this panics is if user manually changes pbs file") == yd_date,
-				None => false,
-			};
+			let is_validated: bool = yd_streaks_source.as_ref().is_some_and(condition);
+			let skip = yd_unparsable
+				|| match pb_streaks.get("__last_date_processed") {
+					Some(v) => v.as_str().expect("The only way this panics is if user manually changes pbs file") == yd_date,
+					None => false,
+				};
 			if !skip {
 				let mut new_streak = if is_validated {
 					Streak {
@@ -412,13 +578,13 @@ impl Day {
 		let no_streak_condition = |d: &Day| d.number_of_NOs > 0;
 		let _ = streak_update("NOs_streak", &no_streak_condition);
 
-		let responsible_caffeine_condition = |d: &Day| d.caffeine_only_during_work == true;
+		let responsible_caffeine_condition = |d: &Day| d.caffeine_only_during_work;
 		let _ = streak_update("responsible_caffeine", &responsible_caffeine_condition);
 
-		let responsible_messengers_condition = |d: &Day| d.checked_messages_only_during_eating == true;
+		let responsible_messengers_condition = |d: &Day| d.checked_messages_only_during_eating;
 		let _ = streak_update("responsible_messengers", &responsible_messengers_condition);
 
-		let running_streak_condition = |d: &Day| d.morning.run == true;
+		let running_streak_condition = |d: &Day| d.morning.run;
 		let _ = streak_update("running_streak", &running_streak_condition);
 
 		let rejection_streak_condition = |d: &Day| d.number_of_rejections > 0;
@@ -427,17 +593,12 @@ impl Day {
 		let locked_phone_streak_condition = |d: &Day| d.phone_locked_away;
 		let _ = streak_update("locked_phone_streak", &locked_phone_streak_condition);
 
-		pbs_as_value["streaks"]["__last_date_processed"] = serde_json::Value::from(yd_date);
+		if !yd_unparsable {
+			pbs_as_value["streaks"]["__last_date_processed"] = serde_json::Value::from(yd_date);
+		}
 
 		let formatted_json = serde_json::to_string_pretty(&pbs_as_value).unwrap();
-		let mut file = OpenOptions::new()
-			.read(true)
-			.write(true)
-			.create(true)
-			.truncate(true) //? what the hell does this do?
-			.open(&pbs_path)
-			.unwrap();
-		file.write_all(formatted_json.as_bytes()).unwrap();
+		utils::atomic_write(&pbs_path, formatted_json).with_context(|| format!("Failed to write {}", pbs_path.display()))
 	}
 }
 
@@ -486,3 +647,167 @@ impl std::fmt::Display for Repercussions {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Cursor;
+
+	fn ev_args(ev: Option<i32>, add: bool) -> ManualEv {
+		ManualEv {
+			ev,
+			open: false,
+			add,
+			subtract: false,
+			replace: true,
+		}
+	}
+
+	#[test]
+	fn test_ev_from_stdin() {
+		let validated = ev_args(None, false).validate(Cursor::new("420\n")).unwrap();
+		assert_eq!(validated.ev, 420);
+		assert_eq!(validated.mode, EvMode::Replace);
+
+		let validated = ev_args(None, true).validate(Cursor::new(" -30 \n")).unwrap();
+		assert_eq!(validated.ev, -30);
+		assert_eq!(validated.mode, EvMode::Add);
+	}
+
+	#[test]
+	fn test_ev_from_stdin_is_recorded() {
+		std::env::set_var("WAKETIME", "05:00");
+		std::env::set_var("DAY_SECTION_BORDERS", "16");
+		crate::mocks::set_timestamp(
+			chrono::NaiveDate::from_ymd_opt(2024, 6, 13)
+				.unwrap()
+				.and_hms_opt(12, 0, 0)
+				.unwrap()
+				.and_utc()
+				.timestamp(),
+		);
+		let dir = tempfile::tempdir().unwrap();
+		let config = AppConfig {
+			data_dir: dir.path().to_path_buf(),
+			date_format: "%Y-%m-%d".to_owned(),
+			..Default::default()
+		};
+		// nothing here can be a new pb, so no notifications are sent
+		let pbs_path = Day::path("2024-06-13", &config).parent().unwrap().join(PBS_FILENAME);
+		std::fs::write(pbs_path, r#"{"ev": 10000, "cw_counter": 0}"#).unwrap();
+		let args = ManualArgs {
+			days_back: None,
+			yesterday: false,
+			command: ManualSubcommands::Ev(ev_args(None, false)),
+		};
+
+		update_or_open_from(config.clone(), args, Cursor::new("420\n")).unwrap();
+
+		assert_eq!(Day::load("2024-06-13", &config).unwrap().ev, 420);
+	}
+
+	#[test]
+	fn test_ev_provided_skips_prompt() {
+		let validated = ev_args(Some(69), false).validate(Cursor::new("420\n")).unwrap();
+		assert_eq!(validated.ev, 69);
+	}
+
+	#[test]
+	fn test_ev_from_stdin_garbage() {
+		assert!(ev_args(None, false).validate(Cursor::new("abc\n")).is_err());
+	}
+
+	#[test]
+	fn test_load_partial_day() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = AppConfig {
+			data_dir: dir.path().to_path_buf(),
+			..Default::default()
+		};
+		let partial = r#"{"date": "2024-06-12", "ev": 300, "morning": {"run": true}, "counters": {"cargo_watch": 7}}"#;
+		std::fs::write(Day::path("2024-06-12", &config), partial).unwrap();
+
+		let day = Day::load("2024-06-12", &config).unwrap();
+		assert_eq!(day.ev, 300);
+		assert!(day.morning.run);
+		assert_eq!(day.counters.cargo_watch, 7);
+		assert_eq!(day.counters.dev_runs, 0);
+		assert_eq!(day.jofv_mins, None);
+
+		std::fs::write(Day::path("2024-06-12", &config), "{ garbage").unwrap();
+		assert!(Day::load("2024-06-12", &config).is_err());
+	}
+
+	#[test]
+	fn test_set_fields_merges() {
+		let mut day = Day {
+			ev: 300,
+			..Default::default()
+		};
+		day.set_fields(&["morning.run=true".to_owned(), "evening.nsdr=20".to_owned()]).unwrap();
+		day.set_fields(&["jofv_mins=0".to_owned(), "morning.eating_food=15".to_owned()]).unwrap();
+
+		assert_eq!(day.ev, 300);
+		assert!(day.morning.run);
+		assert_eq!(day.evening.nsdr, 20);
+		assert_eq!(day.jofv_mins, Some(0));
+		assert_eq!(day.morning.transcendential.eating_food, Some(15));
+
+		assert!(day.set_fields(&["morning.not_a_field=1".to_owned()]).is_err());
+		assert!(day.set_fields(&["evening.nsdr=abc".to_owned()]).is_err());
+		assert!(day.set_fields(&["ev".to_owned()]).is_err());
+		assert!(day.set_fields(&["date=bogus".to_owned()]).is_err());
+		assert_eq!(day.date, "");
+		assert_eq!(day.evening.nsdr, 20);
+	}
+
+	#[test]
+	fn test_days_back() {
+		let args = |days_back: Option<usize>, yesterday: bool| ManualArgs {
+			days_back,
+			yesterday,
+			command: ManualSubcommands::Open(ManualOpen { pbs: false }),
+		};
+		assert_eq!(args(None, false).days_back().unwrap(), 0);
+		assert_eq!(args(None, true).days_back().unwrap(), 1);
+		assert_eq!(args(Some(3), false).days_back().unwrap(), 3);
+		assert_eq!(args(Some(1), true).days_back().unwrap(), 1);
+		assert!(args(Some(3), true).days_back().is_err());
+	}
+
+	#[test]
+	fn test_unparsable_yd_keeps_streaks() {
+		std::env::set_var("WAKETIME", "05:00");
+		std::env::set_var("DAY_SECTION_BORDERS", "16");
+		crate::mocks::set_timestamp(
+			chrono::NaiveDate::from_ymd_opt(2024, 6, 13)
+				.unwrap()
+				.and_hms_opt(12, 0, 0)
+				.unwrap()
+				.and_utc()
+				.timestamp(),
+		);
+		let dir = tempfile::tempdir().unwrap();
+		let config = AppConfig {
+			data_dir: dir.path().to_path_buf(),
+			date_format: "%Y-%m-%d".to_owned(),
+			..Default::default()
+		};
+		let yd_path = Day::path("2024-06-12", &config);
+		let data_storage_dir = yd_path.parent().unwrap();
+		let pbs_path = data_storage_dir.join(PBS_FILENAME);
+		// nothing here can be a new pb, so no notifications are sent
+		std::fs::write(
+			&pbs_path,
+			r#"{"ev": 0, "cw_counter": 0, "streaks": {"math": {"pb": 5, "current": 3}, "__last_date_processed": "2024-06-11"}}"#,
+		)
+		.unwrap();
+		std::fs::write(&yd_path, "{ garbage").unwrap();
+
+		Day::default().update_pbs(data_storage_dir, &config).unwrap();
+
+		let pbs: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&pbs_path).unwrap()).unwrap();
+		assert_eq!(pbs["streaks"]["math"]["current"], 3);
+		assert_eq!(pbs["streaks"]["__last_date_processed"], "2024-06-11");
+	}
+}
@@ -1,12 +1,14 @@
-use crate::config::AppConfig;
-use anyhow::{anyhow, Result};
+use crate::config::{AppConfig, TimerSinkKind};
+use crate::utils::{atomic_write, quarantine_corrupt};
+use anyhow::{anyhow, Context, Result};
 use chrono::prelude::*;
 use clap::Args;
 use clap::Subcommand;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::Read;
+use std::path::Path;
 use std::process::Command;
 
 use crate::ONGOING_PATH_APPENDIX;
@@ -21,30 +23,64 @@ pub fn timing_the_task(config: AppConfig, args: TimerArgs) -> Result<()> {
 
 	let success = match args.command {
 		TimerCommands::Start(start_args) => {
-			if start_args.time > 90 {
+			let max_estimate_minutes = config.timer.max_estimate_minutes;
+			let time = start_args.time.unwrap_or(max_estimate_minutes);
+			if time > max_estimate_minutes {
 				return Err(anyhow!(
-					"Provided time is too large. Cut your task into smaller parts. Anything above 90m does not make sense."
+					"Provided time is too large. Cut your task into smaller parts. Anything above {}m does not make sense.",
+					max_estimate_minutes
 				));
 			}
 
+			let category = match start_args.legacy_category.extract_category_name() {
+				Some(legacy) => legacy,
+				None => start_args.category,
+			};
+			let category = validate_category(&category, &config.timer.categories)?;
+
 			let timestamp_s = Utc::now().timestamp() as u32;
 			let task = Ongoing {
 				timestamp_s,
-				category: start_args.category.extract_category_name(),
-				estimated_minutes: start_args.time,
+				category,
+				estimated_minutes: time,
 				description: start_args.description,
+				pauses: Vec::new(),
 			};
-
-			let mut file = File::create(state_file)?;
-			let serialized = serde_json::to_string(&task).unwrap();
-			file.write_all(serialized.as_bytes()).unwrap();
+			save_ongoing(state_file, &task)?;
 
 			run(&config)
 		}
+		TimerCommands::Pause(_) => {
+			let mut task = load_ongoing(state_file)?;
+			if task.is_paused() {
+				eprintln!("Task is already paused.");
+				return Ok(());
+			}
+			task.pauses.push((Utc::now().timestamp() as u32, None));
+			save_ongoing(state_file, &task)
+		}
+		TimerCommands::Resume(_) => {
+			let mut task = load_ongoing(state_file)?;
+			match task.pauses.last_mut() {
+				Some((_, end @ None)) => *end = Some(Utc::now().timestamp() as u32),
+				_ => {
+					eprintln!("Task is not paused.");
+					return Ok(());
+				}
+			}
+			save_ongoing(state_file, &task)
+		}
 		TimerCommands::Open(_) => v_utils::io::open(&save_file),
 		TimerCommands::Done(_) => save_result(&config, true),
 		TimerCommands::Failed(_) => save_result(&config, false),
 		TimerCommands::ContinueOngoing(_) => run(&config),
+		TimerCommands::Stats(stats_args) => stats(&config, stats_args),
+		TimerCommands::Categories(_) => {
+			for category in &config.timer.categories {
+				println!("{}", category);
+			}
+			Ok(())
+		}
 	};
 
 	success
@@ -63,17 +99,53 @@ enum TimerCommands {
 	Failed(TimerFailedArgs),
 	Open(TimerOpenArgs),
 	ContinueOngoing(TimerContinueArgs),
+	/// Stop counting time on the ongoing task until `resume`
+	Pause(TimerPauseArgs),
+	Resume(TimerResumeArgs),
+	/// How realised time compares to estimates, per category
+	Stats(TimerStatsArgs),
+	/// List the categories `start -c` accepts, as loaded from the config
+	Categories(TimerCategoriesArgs),
 }
 
 #[derive(Args)]
 struct TimerStartArgs {
-	#[arg(short, long, default_value = "90")]
-	time: u32,
+	/// Estimate in minutes. Defaults to `timer.max_estimate_minutes` from the config
+	#[arg(short, long)]
+	time: Option<u32>,
 	#[arg(short, long, default_value = "")]
 	description: String,
+	/// One of `timer.categories` from the config, see `todo timer categories`. Underscores are read as spaces.
+	#[arg(short, long, default_value = "")]
+	category: String,
 	#[clap(flatten)]
-	category: CategoryFlags,
+	legacy_category: LegacyCategoryFlags,
+}
+
+/// `--rust`, `--git-issue` etc, from before categories were configurable. Hidden, kept so existing invocations still work.
+macro_rules! legacy_category_flags {
+	($($name:ident),*) => {
+		#[derive(Args)]
+		struct LegacyCategoryFlags {
+			$(
+			#[arg(long, hide = true, conflicts_with = "category")]
+			$name: bool,
+			)*
+		}
+
+		impl LegacyCategoryFlags {
+			fn extract_category_name(&self) -> Option<String> {
+				match self {
+					$(
+					Self { $name: true, .. } => Some(stringify!($name).to_owned()),
+					)*
+					_ => None,
+				}
+			}
+		}
+	};
 }
+legacy_category_flags!(rust, go, python, home, workout, library, git_issue);
 
 #[derive(Args)]
 struct TimerDoneArgs {}
@@ -83,31 +155,36 @@ struct TimerFailedArgs {}
 struct TimerOpenArgs {}
 #[derive(Args)]
 struct TimerContinueArgs {}
+#[derive(Args)]
+struct TimerPauseArgs {}
+#[derive(Args)]
+struct TimerResumeArgs {}
+#[derive(Args)]
+struct TimerCategoriesArgs {}
+#[derive(Args)]
+struct TimerStatsArgs {
+	#[arg(short, long)]
+	category: Option<String>,
+	/// Only consider the last N recorded tasks
+	#[arg(short, long)]
+	last: Option<usize>,
+}
 
-macro_rules! category_flags {
-	($($name:ident),*) => {
-
-	#[derive(Args)]
-	struct CategoryFlags {
-		$(
-		#[arg(long)]
-		$name: bool,
-		)*
-	}
-
-	impl CategoryFlags {
-		fn extract_category_name(&self) -> String {
-			match self {
-				$(
-				Self { $name: true, .. } => stringify!($name).replace("_", " ").to_owned(),
-				)*
-				_ => "".to_owned(),
-			}
-		}
+fn validate_category(category: &str, categories: &[String]) -> Result<String> {
+	if category.is_empty() {
+		return Ok(String::new());
+	}
+	let category = category.replace('_', " ");
+	match categories.contains(&category) {
+		true => Ok(category),
+		false => Err(anyhow!(
+			"Unknown category \"{}\". Available categories: {}",
+			category,
+			categories.join(", ")
+		)),
 	}
-	};
 }
-category_flags!(rust, go, python, home, workout, library, git_issue);
+
 //-----------------------------------------------------------------------------
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -116,6 +193,18 @@ struct Ongoing {
 	category: String,
 	estimated_minutes: u32,
 	description: String,
+	/// (start_s, end_s) of every pause; end is `None` while paused
+	#[serde(default)]
+	pauses: Vec<(u32, Option<u32>)>,
+}
+impl Ongoing {
+	fn is_paused(&self) -> bool {
+		self.pauses.last().is_some_and(|(_, end)| end.is_none())
+	}
+
+	fn paused_s(&self, now_s: u32) -> u32 {
+		self.pauses.iter().map(|(start, end)| end.unwrap_or(now_s).saturating_sub(*start)).sum()
+	}
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -126,6 +215,44 @@ struct Record {
 	description: String,
 	completed: bool,
 	realised_minutes: u32,
+	/// Ran into the hard stop, so `realised_minutes` is capped. Absent in older records, which read as not hard-stopped.
+	#[serde(default)]
+	hard_stopped: bool,
+}
+
+fn load_ongoing(state_file: &Path) -> Result<Ongoing> {
+	let mut file = File::open(state_file).with_context(|| format!("No ongoing task: failed to open {}", state_file.display()))?;
+	let mut contents = String::new();
+	file.read_to_string(&mut contents)
+		.with_context(|| format!("Failed to read state file {}", state_file.display()))?;
+	match serde_json::from_str(&contents) {
+		Ok(task) => Ok(task),
+		Err(_) => {
+			quarantine_corrupt(state_file).with_context(|| format!("Failed to move corrupted {} aside", state_file.display()))?;
+			Err(anyhow!("No ongoing task: the state file was corrupted"))
+		}
+	}
+}
+
+fn save_ongoing(state_file: &Path, task: &Ongoing) -> Result<()> {
+	let serialized = serde_json::to_string(task)?;
+	atomic_write(state_file, serialized).with_context(|| format!("Failed to write state file {}", state_file.display()))
+}
+
+/// Records of the day. A file that fails to parse is moved aside rather than silently overwritten.
+fn load_records(save_file: &Path) -> Result<VecDeque<Record>> {
+	let contents = match std::fs::read_to_string(save_file) {
+		Ok(contents) => contents,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(VecDeque::new()),
+		Err(e) => return Err(e).with_context(|| format!("Failed to read {}", save_file.display())),
+	};
+	match serde_json::from_str(&contents) {
+		Ok(records) => Ok(records),
+		Err(_) => {
+			quarantine_corrupt(save_file).with_context(|| format!("Failed to move corrupted {} aside", save_file.display()))?;
+			Ok(VecDeque::new())
+		}
+	}
 }
 
 fn save_result(config: &AppConfig, mut completed: bool) -> Result<()> {
@@ -134,19 +261,19 @@ fn save_result(config: &AppConfig, mut completed: bool) -> Result<()> {
 	let save_file = save_dir.join(format!("{}.json", Utc::now().format(&config.date_format)));
 	let hard_stop_coeff = config.timer.hard_stop_coeff;
 
-	let mut file = File::open(state_file).unwrap();
-	let mut contents = String::new();
-	file.read_to_string(&mut contents).unwrap();
-	let ongoing: Ongoing = serde_json::from_str(&contents).unwrap();
+	let ongoing = load_ongoing(state_file)?;
 
-	let realised_minutes = {
-		let diff_m = ((Utc::now().timestamp() as u32 - ongoing.timestamp_s) as f32 / 60.0) as u32;
+	let (realised_minutes, hard_stopped) = {
+		let now_s = Utc::now().timestamp() as u32;
+		let diff_s = now_s - ongoing.timestamp_s - ongoing.paused_s(now_s);
+		let diff_m = (diff_s as f32 / 60.0) as u32;
+		let hard_stop_s = (hard_stop_coeff * (ongoing.estimated_minutes * 60) as f32) as u32;
 		let hard_stop_m = (hard_stop_coeff * ongoing.estimated_minutes as f32 + 0.5) as u32;
-		if hard_stop_m < diff_m {
+		if diff_s > hard_stop_s {
 			completed = false; // It was possible to do `my_todo done` while executable is inactive, passing completed==true here, while far past the hard stop
-			hard_stop_m
+			(diff_m.min(hard_stop_m), true)
 		} else {
-			diff_m
+			(diff_m, false)
 		}
 	};
 	let result = Record {
@@ -156,28 +283,19 @@ fn save_result(config: &AppConfig, mut completed: bool) -> Result<()> {
 		description: ongoing.description,
 		completed,
 		realised_minutes,
+		hard_stopped,
 	};
 
-	let mut results: VecDeque<Record> = match File::open(&save_file) {
-		Ok(mut file) => {
-			let mut contents = String::new();
-			file.read_to_string(&mut contents).unwrap();
-			serde_json::from_str(&contents).unwrap_or_else(|_| VecDeque::new())
-		}
-		Err(_) => VecDeque::new(),
-	};
-
+	let mut results = load_records(&save_file)?;
 	results.push_back(result);
-
-	let mut file = File::create(&save_file).unwrap(); //NB: overrides the existing file if any.
-	file.write_all(serde_json::to_string(&results).unwrap().as_bytes()).unwrap();
+	atomic_write(&save_file, serde_json::to_string(&results)?).with_context(|| format!("Failed to write {}", save_file.display()))?;
 	let _ = std::fs::remove_file(state_file);
 
-	std::thread::sleep(std::time::Duration::from_millis(300)); // wait for eww to process previous request if any.
-	if let Ok(eww_output) = Command::new("sh").arg("-c").arg("eww get todo_timer").output() {
-		let todo_timer = String::from_utf8_lossy(&eww_output.stdout).trim().to_string();
-		if !todo_timer.starts_with("Out") {
-			let _ = Command::new("sh").arg("-c").arg("eww update todo_timer=None").output().unwrap();
+	let sink = timer_sink(config);
+	std::thread::sleep(std::time::Duration::from_millis(300)); // wait for the sink to process previous request if any.
+	if let Some(current) = sink.current() {
+		if !current.starts_with("Out") {
+			sink.update("None")?;
 		}
 	}
 
@@ -187,29 +305,27 @@ fn save_result(config: &AppConfig, mut completed: bool) -> Result<()> {
 fn run(config: &AppConfig) -> Result<()> {
 	let state_file = &config.data_dir.join(ONGOING_PATH_APPENDIX);
 	let hard_stop_coeff = config.timer.hard_stop_coeff;
+	let sink = timer_sink(config);
 
-	let task: Ongoing = {
-		if state_file.exists() {
-			let mut file = File::open(state_file).unwrap();
-			let mut contents = String::new();
-			file.read_to_string(&mut contents).unwrap();
-			serde_json::from_str(&contents).unwrap()
-		} else {
-			eprintln!("No record of an ongoing task found, exiting.");
-			std::process::exit(0);
-		}
-	};
-	let estimated_s = task.timestamp_s + task.estimated_minutes * 60;
-	let hard_stop_s = task.timestamp_s + (hard_stop_coeff * (task.estimated_minutes * 60) as f32) as u32;
+	if !state_file.exists() {
+		eprintln!("No record of an ongoing task found, exiting.");
+		return Ok(());
+	}
 
 	loop {
-		if !state_file.exists() {
-			break;
-		}
+		// re-read every tick, as `pause`/`resume`/`done` are recorded from other processes
+		let task = match load_ongoing(state_file) {
+			Ok(task) => task,
+			Err(_) if !state_file.exists() => break,
+			Err(e) => return Err(e),
+		};
+		let estimated_s = task.timestamp_s + task.estimated_minutes * 60;
+		let hard_stop_s = task.timestamp_s + (hard_stop_coeff * (task.estimated_minutes * 60) as f32) as u32;
 
 		let now_s = Utc::now().timestamp();
-		let e_diff = estimated_s as i64 - now_s;
-		let h_diff = hard_stop_s as i64 - now_s;
+		let paused_s = task.paused_s(now_s as u32) as i64;
+		let e_diff = estimated_s as i64 + paused_s - now_s;
+		let h_diff = hard_stop_s as i64 + paused_s - now_s;
 		let description = {
 			if task.description.as_str() == "" {
 				String::new()
@@ -218,7 +334,9 @@ fn run(config: &AppConfig) -> Result<()> {
 			}
 		};
 
-		let value = if h_diff < 0 {
+		let value = if task.is_paused() {
+			format!("Paused{}", description)
+		} else if h_diff < 0 {
 			format!("Out{}", description)
 		} else if e_diff < 0 {
 			format!("-{:02}:{:02}{}", h_diff / 60, h_diff % 60, description)
@@ -226,17 +344,280 @@ fn run(config: &AppConfig) -> Result<()> {
 			format!("{:02}:{:02}{}", e_diff / 60, e_diff % 60, description)
 		};
 
-		let _ = Command::new("sh")
-			.arg("-c")
-			.arg(format!("eww update todo_timer=\"{}\"", value))
-			.output()
-			.unwrap();
+		sink.update(&value)?;
 		if value.starts_with("Out") {
-			save_result(config, false)?;
-			std::process::exit(0);
+			return save_result(config, false);
 		}
 		std::thread::sleep(std::time::Duration::from_secs(1));
 	}
 
 	Ok(())
 }
+
+//-----------------------------------------------------------------------------
+
+/// Every day file in `save_dir`, oldest first. Anything other than a `.json` file (eg quarantined `.corrupt-<ts>` ones) is not a day file.
+fn load_all_records(save_dir: &Path) -> Result<Vec<Record>> {
+	let mut records: Vec<Record> = Vec::new();
+	for entry in std::fs::read_dir(save_dir).with_context(|| format!("Failed to read {}", save_dir.display()))? {
+		let path = entry?.path();
+		if !path.is_file() || path.extension().is_none_or(|ext| ext != "json") {
+			continue;
+		}
+		let contents = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+		match serde_json::from_str::<Vec<Record>>(&contents) {
+			Ok(day) => records.extend(day),
+			Err(e) => eprintln!("Warning: skipping {}: {}", path.display(), e),
+		}
+	}
+	records.sort_by_key(|r| r.timestamp_s);
+	Ok(records)
+}
+
+fn stats(config: &AppConfig, args: TimerStatsArgs) -> Result<()> {
+	let mut records = load_all_records(&config.data_dir.join(TIMED_PATH_APPENDIX))?;
+
+	if let Some(category) = &args.category {
+		let category = category.replace('_', " ");
+		records.retain(|r| r.category == category);
+	}
+	if let Some(n) = args.last {
+		let cut_index = records.len().saturating_sub(n);
+		records.drain(..cut_index);
+	}
+
+	let stats = CategoryStats::collect(&records);
+	if stats.is_empty() {
+		println!("no data");
+		return Ok(());
+	}
+	println!(
+		"{:<15} {:>6} {:>12} {:>12} {:>10} {:>13}",
+		"category", "tasks", "mean ratio", "median ratio", "completed", "hard stopped"
+	);
+	for s in stats {
+		println!(
+			"{:<15} {:>6} {:>12.2} {:>12.2} {:>9.0}% {:>13}",
+			s.category,
+			s.n,
+			s.mean_ratio,
+			s.median_ratio,
+			s.completion_rate * 100.0,
+			s.n_hard_stopped
+		);
+	}
+	Ok(())
+}
+
+/// Ratios are realised/estimated minutes. Hard-stopped tasks have realised capped at the hard stop, so they only bound the ratio from below, and are left out of it.
+#[derive(Debug, PartialEq)]
+struct CategoryStats {
+	category: String,
+	n: usize,
+	mean_ratio: f32,
+	median_ratio: f32,
+	completion_rate: f32,
+	n_hard_stopped: usize,
+}
+impl CategoryStats {
+	fn collect(records: &[Record]) -> Vec<Self> {
+		let mut categories: Vec<&str> = records.iter().map(|r| r.category.as_str()).collect();
+		categories.sort();
+		categories.dedup();
+
+		categories
+			.into_iter()
+			.map(|category| {
+				let in_category: Vec<&Record> = records.iter().filter(|r| r.category == category).collect();
+				let mut ratios: Vec<f32> = in_category
+					.iter()
+					.filter(|r| r.estimated_minutes > 0 && !r.hard_stopped)
+					.map(|r| r.realised_minutes as f32 / r.estimated_minutes as f32)
+					.collect();
+				ratios.sort_by(|a, b| a.total_cmp(b));
+
+				let mean_ratio = match ratios.len() {
+					0 => f32::NAN,
+					n => ratios.iter().sum::<f32>() / n as f32,
+				};
+				let median_ratio = match ratios.len() {
+					0 => f32::NAN,
+					n if n % 2 == 0 => (ratios[n / 2 - 1] + ratios[n / 2]) / 2.0,
+					n => ratios[n / 2],
+				};
+				let n_completed = in_category.iter().filter(|r| r.completed).count();
+				let n_hard_stopped = in_category.iter().filter(|r| r.hard_stopped).count();
+
+				CategoryStats {
+					category: match category {
+						"" => "(none)".to_owned(),
+						c => c.to_owned(),
+					},
+					n: in_category.len(),
+					mean_ratio,
+					median_ratio,
+					completion_rate: n_completed as f32 / in_category.len() as f32,
+					n_hard_stopped,
+				}
+			})
+			.collect()
+	}
+}
+
+//-----------------------------------------------------------------------------
+
+/// Displays the state of the running timer, eg in a status bar.
+pub trait TimerSink {
+	fn update(&self, value: &str) -> Result<()>;
+	/// What is currently displayed, if the sink can tell.
+	fn current(&self) -> Option<String>;
+}
+
+fn timer_sink(config: &AppConfig) -> Box<dyn TimerSink> {
+	match config.timer.sink {
+		TimerSinkKind::Eww => Box::new(EwwSink),
+		TimerSinkKind::Null => Box::new(NullSink),
+	}
+}
+
+/// Writes to the `todo_timer` eww variable.
+pub struct EwwSink;
+impl TimerSink for EwwSink {
+	fn update(&self, value: &str) -> Result<()> {
+		Command::new("sh")
+			.arg("-c")
+			.arg(format!("eww update todo_timer=\"{}\"", value))
+			.output()
+			.context("Failed to update eww todo_timer")?;
+		Ok(())
+	}
+
+	fn current(&self) -> Option<String> {
+		let output = Command::new("sh").arg("-c").arg("eww get todo_timer").output().ok()?;
+		Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+	}
+}
+
+pub struct NullSink;
+impl TimerSink for NullSink {
+	fn update(&self, _value: &str) -> Result<()> {
+		Ok(())
+	}
+
+	fn current(&self) -> Option<String> {
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_paused_s() {
+		let mut task: Ongoing = serde_json::from_str(r#"{"timestamp_s":0,"category":"","estimated_minutes":30,"description":""}"#).unwrap();
+		assert!(task.pauses.is_empty());
+		assert_eq!(task.paused_s(1000), 0);
+
+		task.pauses.push((100, Some(160)));
+		task.pauses.push((500, None));
+		assert!(task.is_paused());
+		assert_eq!(task.paused_s(1000), 60 + 500);
+	}
+
+	#[test]
+	fn test_validate_category() {
+		let categories = vec!["rust".to_owned(), "git issue".to_owned()];
+		assert_eq!(validate_category("rust", &categories).unwrap(), "rust");
+		assert_eq!(validate_category("git_issue", &categories).unwrap(), "git issue");
+		assert_eq!(validate_category("", &categories).unwrap(), "");
+
+		let e = validate_category("go", &categories).unwrap_err().to_string();
+		assert!(e.contains("rust, git issue"), "{e}");
+	}
+
+	#[test]
+	fn test_category_stats() {
+		let record = |category: &str, estimated_minutes: u32, realised_minutes: u32, completed: bool, hard_stopped: bool| Record {
+			timestamp_s: 0,
+			category: category.to_owned(),
+			estimated_minutes,
+			description: String::new(),
+			completed,
+			realised_minutes,
+			hard_stopped,
+		};
+		let records = vec![
+			record("rust", 10, 10, true, false),
+			record("rust", 10, 20, true, false),
+			record("rust", 20, 30, false, true),
+			record("rust", 20, 30, false, false), // failed right at where the hard stop would be
+			record("", 30, 15, true, false),
+		];
+
+		let stats = CategoryStats::collect(&records);
+		assert_eq!(
+			stats,
+			vec![
+				CategoryStats {
+					category: "(none)".to_owned(),
+					n: 1,
+					mean_ratio: 0.5,
+					median_ratio: 0.5,
+					completion_rate: 1.0,
+					n_hard_stopped: 0,
+				},
+				CategoryStats {
+					category: "rust".to_owned(),
+					n: 4,
+					mean_ratio: (1.0 + 2.0 + 1.5) / 3.0,
+					median_ratio: 1.5,
+					completion_rate: 2.0 / 4.0,
+					n_hard_stopped: 1,
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn test_load_records_recovers_truncated() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("2024-06-12.json");
+		let full = r#"[{"timestamp_s":0,"category":"","estimated_minutes":30,"description":"","completed":true,"realised_minutes":25}]"#;
+		std::fs::write(&path, full).unwrap();
+		assert_eq!(load_records(&path).unwrap().len(), 1);
+
+		std::fs::write(&path, &full[..full.len() / 2]).unwrap();
+		assert!(load_records(&path).unwrap().is_empty());
+		assert!(!path.exists());
+		let quarantined: Vec<_> = std::fs::read_dir(dir.path())
+			.unwrap()
+			.map(|e| e.unwrap().file_name().into_string().unwrap())
+			.collect();
+		assert_eq!(quarantined.len(), 1);
+		assert!(quarantined[0].starts_with("2024-06-12.json.corrupt-"));
+	}
+
+	#[test]
+	fn test_load_all_records_only_reads_day_files() {
+		let dir = tempfile::tempdir().unwrap();
+		let day = r#"[{"timestamp_s":0,"category":"","estimated_minutes":30,"description":"","completed":true,"realised_minutes":25}]"#;
+		std::fs::write(dir.path().join("2024-06-12.json"), day).unwrap();
+		std::fs::write(dir.path().join("2024-06-11.json.corrupt-0"), &day[..10]).unwrap();
+		std::fs::write(dir.path().join("2024-06-11.json.tmp-42"), &day[..10]).unwrap();
+		std::fs::create_dir(dir.path().join("archive")).unwrap();
+
+		assert_eq!(load_all_records(dir.path()).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn test_load_ongoing_quarantines_corrupt() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("timer_ongoing.json");
+		std::fs::write(&path, r#"{"timestamp_s":0,"categ"#).unwrap();
+
+		assert!(load_ongoing(&path).is_err());
+		assert!(!path.exists());
+		assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+	}
+}
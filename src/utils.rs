@@ -5,6 +5,9 @@ use chrono::Utc;
 
 use crate::config::AppConfig;
 use chrono::Duration;
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 pub fn format_date(days_back: usize, config: &AppConfig) -> String {
 	let date = Utc::now() - Duration::days(days_back as i64);
@@ -30,6 +33,31 @@ pub fn same_day_buffer() -> chrono::TimeDelta {
 	new_day - chrono::NaiveTime::from_hms_opt(0, 0, 0).unwrap()
 }
 
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+	let mut name: OsString = path.as_os_str().to_owned();
+	name.push(suffix);
+	PathBuf::from(name)
+}
+
+/// Writes to `<path>.tmp-<pid>`, fsyncs and renames over `path`, so a crash mid-write never leaves a truncated file behind.
+pub fn atomic_write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> std::io::Result<()> {
+	let path = path.as_ref();
+	let tmp = sibling_path(path, &format!(".tmp-{}", std::process::id()));
+	let mut file = std::fs::File::create(&tmp)?;
+	file.write_all(contents.as_ref())?;
+	file.sync_all()?;
+	std::fs::rename(&tmp, path)
+}
+
+/// Moves an unparsable file to `<path>.corrupt-<timestamp>`, so the caller can start fresh without destroying what was there.
+pub fn quarantine_corrupt<P: AsRef<Path>>(path: P) -> std::io::Result<PathBuf> {
+	let path = path.as_ref();
+	let corrupt = sibling_path(path, &format!(".corrupt-{}", Utc::now().timestamp()));
+	std::fs::rename(path, &corrupt)?;
+	eprintln!("Warning: {} could not be parsed, moved it to {}", path.display(), corrupt.display());
+	Ok(corrupt)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -78,4 +106,15 @@ mod tests {
 
 		assert_eq!(formatted_date, "2024-05-29");
 	}
+
+	#[test]
+	fn test_atomic_write_leaves_no_tmp() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("f.json");
+		std::fs::write(&path, "old").unwrap();
+
+		atomic_write(&path, "new").unwrap();
+		assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+		assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+	}
 }